@@ -1,6 +1,12 @@
 use std::error::Error;
 use std::str::from_utf8;
 
+#[cfg(feature = "serde")]
+mod de;
+
+#[cfg(feature = "serde")]
+pub use de::Error as DeserializeError;
+
 #[derive(Debug, Clone)]
 pub enum LuaType {
     Nil,
@@ -8,6 +14,7 @@ pub enum LuaType {
     Integer(i64),
     Number(f64),
     String(String),
+    Array(Vec<LuaType>),
     Table(std::collections::HashMap<String, LuaType>),
 }
 
@@ -84,6 +91,80 @@ impl LuaConvert for std::collections::HashMap<String, LuaType> {
     }
 }
 
+impl<T: LuaConvert> LuaConvert for Option<T> {
+    fn from_lua_type(lua_type: &LuaType) -> Option<Self> {
+        match lua_type {
+            LuaType::Nil => Some(None),
+            other => T::from_lua_type(other).map(Some),
+        }
+    }
+}
+
+impl<T: LuaConvert> LuaConvert for Vec<T> {
+    fn from_lua_type(lua_type: &LuaType) -> Option<Self> {
+        let elements = match lua_type {
+            LuaType::Array(elements) => elements,
+            // An empty table is ambiguous between a map and a sequence, so
+            // accept it as an empty vector for round-trip consistency.
+            LuaType::Table(map) if map.is_empty() => return Some(Vec::new()),
+            _ => return None,
+        };
+
+        let mut result = Vec::with_capacity(elements.len());
+        for element in elements {
+            result.push(T::from_lua_type(element)?);
+        }
+        Some(result)
+    }
+}
+
+impl<T: LuaConvert> LuaConvert for std::collections::HashMap<String, T> {
+    fn from_lua_type(lua_type: &LuaType) -> Option<Self> {
+        let table = match lua_type {
+            LuaType::Table(table) => table,
+            _ => return None,
+        };
+
+        let mut result = std::collections::HashMap::with_capacity(table.len());
+        for (key, value) in table.iter() {
+            result.insert(key.clone(), T::from_lua_type(value)?);
+        }
+        Some(result)
+    }
+}
+
+macro_rules! count_idents {
+    () => (0usize);
+    ($head:ident $(, $tail:ident)*) => (1usize + count_idents!($($tail),*));
+}
+
+// Fixed-size tuples convert from a Lua sequence of matching arity, element by
+// element, the way mlua turns a tuple into a table of values.
+macro_rules! impl_lua_convert_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: LuaConvert),+> LuaConvert for ($($name,)+) {
+            fn from_lua_type(lua_type: &LuaType) -> Option<Self> {
+                let items = match lua_type {
+                    LuaType::Array(items) => items,
+                    _ => return None,
+                };
+                if items.len() != count_idents!($($name),+) {
+                    return None;
+                }
+                let mut iter = items.iter();
+                Some(($($name::from_lua_type(iter.next()?)?,)+))
+            }
+        }
+    };
+}
+
+impl_lua_convert_tuple!(A);
+impl_lua_convert_tuple!(A, B);
+impl_lua_convert_tuple!(A, B, C);
+impl_lua_convert_tuple!(A, B, C, D);
+impl_lua_convert_tuple!(A, B, C, D, E);
+impl_lua_convert_tuple!(A, B, C, D, E, F);
+
 fn print_lua_type(value: LuaType, f: &mut std::fmt::Formatter, depth: usize) -> std::fmt::Result {
     match value {
         LuaType::Nil => write!(f, "nil"),
@@ -91,6 +172,14 @@ fn print_lua_type(value: LuaType, f: &mut std::fmt::Formatter, depth: usize) ->
         LuaType::Integer(n) => write!(f, "Integer({})", n),
         LuaType::Number(n) => write!(f, "Number({})", n),
         LuaType::String(s) => write!(f, "String(\"{}\")", s),
+        LuaType::Array(items) => {
+            write!(f, "[")?;
+            for (index, value) in items.iter().enumerate() {
+                write!(f, "\n{}{} = ", " ".repeat(depth * 4), index + 1)?;
+                print_lua_type(value.clone(), f, depth + 1)?;
+            }
+            write!(f, "\n{}]", " ".repeat((depth - 1) * 4))
+        }
         LuaType::Table(map) => {
             write!(f, "{{")?;
             for (key, value) in map.iter() {
@@ -108,10 +197,34 @@ impl std::fmt::Display for LuaType {
     }
 }
 
+/// A host-provided function callable from config scripts.
+///
+/// Arguments arrive already converted to [`LuaType`] and the return value is
+/// converted back before it reaches Lua; an `Err` becomes a Lua runtime error.
+pub type HostFunction =
+    std::sync::Arc<dyn Fn(Vec<LuaType>) -> Result<LuaType, String> + Send + Sync + 'static>;
+
 pub struct LuaConfig {
     pub data: std::collections::HashMap<String, LuaType>,
     config: String,
     default: Option<String>,
+    functions: Vec<(String, HostFunction)>,
+    globals: Vec<(String, LuaType)>,
+    sandbox: Option<Sandbox>,
+}
+
+/// Restrictions applied to config execution in sandboxed mode.
+///
+/// Created through [`LuaConfig::sandboxed`] and refined with
+/// [`LuaConfig::with_instruction_limit`] and [`LuaConfig::allow_fetch`].
+#[derive(Debug, Clone, Default)]
+struct Sandbox {
+    /// Maximum number of Lua VM instructions before execution is aborted.
+    instruction_limit: Option<u32>,
+    /// Whether the `fetch_data` network helper is available at all.
+    allow_fetch: bool,
+    /// If set, `fetch_data` may only reach these hosts.
+    allowed_hosts: Option<Vec<String>>,
 }
 
 impl LuaConfig {
@@ -120,7 +233,84 @@ impl LuaConfig {
             data: std::collections::HashMap::new(),
             config: file,
             default: None,
+            functions: Vec::new(),
+            globals: Vec::new(),
+            sandbox: None,
+        }
+    }
+
+    /// Execute the config in a restricted environment.
+    ///
+    /// The Lua state is built without the `io`, `os`, `package` and `debug`
+    /// libraries, and the `fetch_data` network helper is omitted unless
+    /// re-enabled with [`allow_fetch`]. Combine with [`with_instruction_limit`]
+    /// to bound run time. Use this to load config from untrusted third parties.
+    ///
+    /// [`with_instruction_limit`]: LuaConfig::with_instruction_limit
+    /// [`allow_fetch`]: LuaConfig::allow_fetch
+    pub fn sandboxed(mut self) -> Self {
+        self.sandbox = Some(Sandbox::default());
+        self
+    }
+
+    /// Abort execution after `limit` Lua VM instructions.
+    ///
+    /// Only meaningful together with [`sandboxed`]; guards against config
+    /// scripts that loop forever.
+    ///
+    /// [`sandboxed`]: LuaConfig::sandboxed
+    pub fn with_instruction_limit(mut self, limit: u32) -> Self {
+        debug_assert!(
+            self.sandbox.is_some(),
+            "with_instruction_limit has no effect without sandboxed()"
+        );
+        if let Some(sandbox) = self.sandbox.as_mut() {
+            sandbox.instruction_limit = Some(limit);
+        }
+        self
+    }
+
+    /// Re-enable the `fetch_data` network helper inside a sandbox.
+    ///
+    /// When `hosts` is non-empty, `fetch_data` may only reach the listed
+    /// hosts; an empty slice allows any host.
+    ///
+    /// Only meaningful together with [`sandboxed`].
+    ///
+    /// [`sandboxed`]: LuaConfig::sandboxed
+    pub fn allow_fetch(mut self, hosts: &[&str]) -> Self {
+        debug_assert!(
+            self.sandbox.is_some(),
+            "allow_fetch has no effect without sandboxed()"
+        );
+        if let Some(sandbox) = self.sandbox.as_mut() {
+            sandbox.allow_fetch = true;
+            sandbox.allowed_hosts = if hosts.is_empty() {
+                None
+            } else {
+                Some(hosts.iter().map(|h| h.to_string()).collect())
+            };
         }
+        self
+    }
+
+    /// Register a Rust function that config scripts can call by `name`.
+    ///
+    /// The closure receives the call arguments as [`LuaType`] values and
+    /// returns a [`LuaType`]; returning `Err` surfaces as a Lua error.
+    pub fn with_function<F>(mut self, name: &str, function: F) -> Self
+    where
+        F: Fn(Vec<LuaType>) -> Result<LuaType, String> + Send + Sync + 'static,
+    {
+        self.functions
+            .push((name.to_string(), std::sync::Arc::new(function)));
+        self
+    }
+
+    /// Register a global value that config scripts can read by `name`.
+    pub fn with_global(mut self, name: &str, value: LuaType) -> Self {
+        self.globals.push((name.to_string(), value));
+        self
     }
 
     pub fn from_file(path: &str) -> Result<Self, Box<dyn Error>> {
@@ -134,17 +324,49 @@ impl LuaConfig {
     }
 
     pub fn execute(mut self) -> Result<Self, Box<dyn Error>> {
-        let lua = rlua::Lua::new();
-        let config_values = LuaConfig::get_hashmap_by_function(&lua, &self.config, "Config")?;
+        let lua = match &self.sandbox {
+            // A safe subset of the stdlib: the base library is always present;
+            // `io`, `os`, `package` and `debug` are left out.
+            Some(_) => rlua::Lua::new_with(
+                rlua::StdLib::TABLE | rlua::StdLib::STRING | rlua::StdLib::MATH,
+                rlua::LuaOptions::default(),
+            )?,
+            None => rlua::Lua::new(),
+        };
+
+        if let Some(limit) = self.sandbox.as_ref().and_then(|s| s.instruction_limit) {
+            // Interrupt long-running scripts by counting VM instructions. Fire
+            // the hook on a step small enough not to overrun a tight limit.
+            let step = std::cmp::max(1, std::cmp::min(limit, 1000));
+            let counter = std::sync::atomic::AtomicU32::new(0);
+            lua.set_hook(
+                rlua::HookTriggers {
+                    every_nth_instruction: Some(step),
+                    ..Default::default()
+                },
+                move |_ctx, _debug| {
+                    let seen = counter
+                        .fetch_add(step, std::sync::atomic::Ordering::Relaxed)
+                        .saturating_add(step);
+                    if seen >= limit {
+                        Err(rlua::Error::RuntimeError(format!(
+                            "instruction limit of {} exceeded",
+                            limit
+                        )))
+                    } else {
+                        Ok(())
+                    }
+                },
+            );
+        }
+
+        let config_values = self.get_hashmap_by_function(&lua, &self.config, "Config")?;
         let mut resulting_values: std::collections::HashMap<String, rlua::Value> =
             std::collections::HashMap::new();
 
         if self.default.is_some() {
-            let default_values = LuaConfig::get_hashmap_by_function(
-                &lua,
-                &self.default.clone().unwrap(),
-                "Default",
-            )?;
+            let default_values =
+                self.get_hashmap_by_function(&lua, &self.default.clone().unwrap(), "Default")?;
 
             for (key, _value) in config_values.iter() {
                 if !default_values.contains_key(key) {
@@ -185,55 +407,192 @@ impl LuaConfig {
         }
     }
 
+    /// Deserialize the whole resolved config into a user type in one shot.
+    ///
+    /// The top-level config table is treated as a map, so `T` is typically a
+    /// struct whose fields line up with the config keys. Nested structs,
+    /// `Vec`, `Option` and map fields all deserialize naturally.
+    #[cfg(feature = "serde")]
+    pub fn deserialize<T>(&self) -> Result<T, de::Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let root = LuaType::Table(self.data.clone());
+        T::deserialize(&root)
+    }
+
+    /// Look up a value by a `/`-separated path.
+    ///
+    /// Intermediate segments descend into nested tables, and numeric segments
+    /// index into arrays using 1-based positions to match Lua (e.g.
+    /// `"servers/1/host"`). Returns `None` on a missing key, an out-of-range
+    /// index, or a type mismatch along the path.
     pub fn get_lua_type(&self, key: &str) -> Option<&LuaType> {
-        let mut map = &self.data;
-        for k in key.split('/') {
-            map = match map.get(k) {
-                Some(value) => {
-                    if let LuaType::Table(m) = value {
-                        m
-                    } else {
+        let mut segments = key.split('/');
+        let first = segments.next()?;
+        let mut current = self.data.get(first)?;
+
+        for segment in segments {
+            current = match current {
+                LuaType::Table(map) => map.get(segment)?,
+                LuaType::Array(items) => {
+                    let index = segment.parse::<usize>().ok()?;
+                    if index == 0 {
                         return None;
                     }
+                    items.get(index - 1)?
                 }
-                None => return None,
+                _ => return None,
             };
         }
 
-        None
+        Some(current)
     }
 
-    fn declare_lua_functions(ctx: &rlua::Context) -> Result<(), rlua::Error> {
+    fn declare_lua_functions(&self, ctx: &rlua::Context) -> Result<(), rlua::Error> {
         let _globals = ctx.globals();
 
-        let fetch_data = ctx.create_function(|lua_ctx, url: String| {
-            let response = reqwest::blocking::get(url).expect("Failed to fetch data");
-            let table = LuaConfig::lua_table_from_json(lua_ctx, &response.text().unwrap())
-                .expect("Failed to convert JSON to Lua table");
-            Ok(table)
-        })?;
-        _globals.set("fetch_data", fetch_data)?;
+        // Outside a sandbox `fetch_data` is always available; inside one it is
+        // only exposed when explicitly opted in, optionally restricted to an
+        // allowlist of hosts.
+        let fetch_allowed = match &self.sandbox {
+            Some(sandbox) => sandbox.allow_fetch,
+            None => true,
+        };
+        if fetch_allowed {
+            let allowed_hosts = self
+                .sandbox
+                .as_ref()
+                .and_then(|s| s.allowed_hosts.clone());
+            let fetch_data = ctx.create_function(move |lua_ctx, url: String| {
+                if let Some(hosts) = &allowed_hosts {
+                    let host = reqwest::Url::parse(&url)
+                        .ok()
+                        .and_then(|u| u.host_str().map(|h| h.to_string()));
+                    let allowed = host
+                        .as_ref()
+                        .map(|h| hosts.iter().any(|allowed| allowed == h))
+                        .unwrap_or(false);
+                    if !allowed {
+                        return Err(rlua::Error::RuntimeError(format!(
+                            "fetch_data: host is not in the allowlist: {}",
+                            url
+                        )));
+                    }
+                }
+                let response = reqwest::blocking::get(url)
+                    .map_err(|e| rlua::Error::RuntimeError(format!("fetch_data request failed: {}", e)))?;
+                let body = response
+                    .text()
+                    .map_err(|e| rlua::Error::RuntimeError(format!("fetch_data body read failed: {}", e)))?;
+                let table = LuaConfig::lua_table_from_json(lua_ctx, &body).map_err(|e| {
+                    rlua::Error::RuntimeError(format!("fetch_data JSON conversion failed: {}", e))
+                })?;
+                Ok(table)
+            })?;
+            _globals.set("fetch_data", fetch_data)?;
+        }
+
+        // Inject user-registered globals and functions.
+        for (name, value) in self.globals.iter() {
+            _globals.set(name.as_str(), LuaConfig::lua_type_to_value(ctx, value)?)?;
+        }
+
+        for (name, function) in self.functions.iter() {
+            // Clone the shared handle into the closure so it owns a `'static`
+            // call target for the lifetime of the Lua state.
+            let function = function.clone();
+            let lua_function = ctx.create_function(move |lua_ctx, args: rlua::MultiValue| {
+                let converted: Vec<LuaType> =
+                    args.iter().map(LuaConfig::value_to_lua_type_free).collect();
+                match function(converted) {
+                    Ok(result) => LuaConfig::lua_type_to_value(&lua_ctx, &result),
+                    Err(message) => Err(rlua::Error::RuntimeError(message)),
+                }
+            })?;
+            _globals.set(name.as_str(), lua_function)?;
+        }
 
         Ok(())
     }
 
+    /// Convert a [`LuaType`] back into an `rlua` value for injection into the
+    /// Lua state.
+    fn lua_type_to_value<'lua>(
+        ctx: &rlua::Context<'lua>,
+        value: &LuaType,
+    ) -> Result<rlua::Value<'lua>, rlua::Error> {
+        Ok(match value {
+            LuaType::Nil => rlua::Value::Nil,
+            LuaType::Boolean(b) => rlua::Value::Boolean(*b),
+            LuaType::Integer(i) => rlua::Value::Integer(*i),
+            LuaType::Number(n) => rlua::Value::Number(*n),
+            LuaType::String(s) => rlua::Value::String(ctx.create_string(s)?),
+            LuaType::Array(items) => {
+                let table = ctx.create_table()?;
+                for (index, item) in items.iter().enumerate() {
+                    table.set(index + 1, LuaConfig::lua_type_to_value(ctx, item)?)?;
+                }
+                rlua::Value::Table(table)
+            }
+            LuaType::Table(map) => {
+                let table = ctx.create_table()?;
+                for (key, item) in map.iter() {
+                    table.set(key.as_str(), LuaConfig::lua_type_to_value(ctx, item)?)?;
+                }
+                rlua::Value::Table(table)
+            }
+        })
+    }
+
     fn lua_table_from_json<'lua>(
         lua: &'lua rlua::Lua,
         json: &str,
     ) -> Result<rlua::Table<'lua>, Box<dyn Error>> {
         let json = json::parse(json)?;
 
+        // Combine a sign and an unsigned magnitude into an `i64` (`i64::MIN`
+        // included), returning `None` when the magnitude does not fit.
+        fn signed_i64(positive: bool, magnitude: u64) -> Option<i64> {
+            if positive {
+                i64::try_from(magnitude).ok()
+            } else if magnitude <= i64::MAX as u64 {
+                Some(-(magnitude as i64))
+            } else if magnitude == i64::MAX as u64 + 1 {
+                Some(i64::MIN)
+            } else {
+                None
+            }
+        }
+
         fn convert_json_to_lua<'lua>(
             lua: &'lua rlua::Lua,
             json_value: &json::JsonValue,
         ) -> Result<rlua::Value<'lua>, Box<dyn Error>> {
-            println!("{:?}", json_value);
             match json_value {
                 json::JsonValue::Null => Ok(rlua::Value::Nil),
+                json::JsonValue::Short(s) => Ok(rlua::Value::String(lua.create_string(s.as_str())?)),
                 json::JsonValue::String(s) => Ok(rlua::Value::String(lua.create_string(s)?)),
-                json::JsonValue::Number(n) => Ok(rlua::Value::Number(
-                    n.as_fixed_point_i64(0).unwrap_or_default() as f64,
-                )),
+                json::JsonValue::Number(n) => {
+                    // Decide integer-vs-float from the decimal parts directly so
+                    // large `i64` magnitudes (> 2^53) are not mangled by a lossy
+                    // f64 roundtrip. `value = ±mantissa * 10^exponent`.
+                    let (positive, mantissa, exponent) = n.as_parts();
+                    let integer = if exponent == 0 {
+                        signed_i64(positive, mantissa)
+                    } else if exponent > 0 {
+                        10u64
+                            .checked_pow(exponent as u32)
+                            .and_then(|scale| mantissa.checked_mul(scale))
+                            .and_then(|m| signed_i64(positive, m))
+                    } else {
+                        None
+                    };
+                    match integer {
+                        Some(i) => Ok(rlua::Value::Integer(i)),
+                        None => Ok(rlua::Value::Number((*n).into())),
+                    }
+                }
                 json::JsonValue::Boolean(b) => Ok(rlua::Value::Boolean(*b)),
                 json::JsonValue::Object(obj) => {
                     let table = lua.create_table()?;
@@ -243,13 +602,20 @@ impl LuaConfig {
                     Ok(rlua::Value::Table(table))
                 }
                 json::JsonValue::Array(arr) => {
+                    // Lua sequences cannot hold `nil` without creating holes, so
+                    // JSON `null` elements are skipped and the remaining items
+                    // are re-indexed into a contiguous `1..n` range.
                     let table = lua.create_table()?;
-                    for (i, value) in arr.iter().enumerate() {
-                        table.set(i + 1, convert_json_to_lua(lua, value)?)?;
+                    let mut index = 1;
+                    for value in arr.iter() {
+                        if value.is_null() {
+                            continue;
+                        }
+                        table.set(index, convert_json_to_lua(lua, value)?)?;
+                        index += 1;
                     }
                     Ok(rlua::Value::Table(table))
                 }
-                _ => unimplemented!("This datatype is not implemented yet"),
             }
         }
 
@@ -263,12 +629,13 @@ impl LuaConfig {
     }
 
     fn get_hashmap_by_function<'lua>(
+        &self,
         lua: &'lua rlua::Lua,
         code: &str,
         function_name: &str,
     ) -> Result<std::collections::HashMap<String, rlua::Value<'lua>>, Box<dyn Error>> {
         let ctx = lua.load(code);
-        LuaConfig::declare_lua_functions(&lua).unwrap();
+        self.declare_lua_functions(&lua)?;
 
         ctx.exec()?;
         let globals = lua.globals();
@@ -299,6 +666,10 @@ impl LuaConfig {
     }
 
     fn value_to_lua_type(&self, value: &rlua::Value) -> LuaType {
+        LuaConfig::value_to_lua_type_free(value)
+    }
+
+    fn value_to_lua_type_free(value: &rlua::Value) -> LuaType {
         match value {
             rlua::Value::Nil => LuaType::Nil,
             rlua::Value::Boolean(b) => LuaType::Boolean(*b),
@@ -306,13 +677,35 @@ impl LuaConfig {
             rlua::Value::Number(n) => LuaType::Number(*n),
             rlua::Value::String(s) => LuaType::String(s.to_str().unwrap_or_default().to_owned()),
             rlua::Value::Table(table) => {
-                let mut map = std::collections::HashMap::new();
-                for pair in table.clone().pairs::<String, rlua::Value>() {
-                    if let Ok((key, value)) = pair {
-                        map.insert(key, self.value_to_lua_type(&value));
+                // A table with a non-empty sequence part (`#t > 0`) becomes an
+                // `Array` so integer keys survive the conversion; note that any
+                // non-sequence (string) keys on a mixed table like
+                // `{1, 2, name = "x"}` are dropped, matching Lua's `#` length
+                // semantics. Every other table (including the empty table)
+                // becomes a string-keyed `Table`.
+                let len = table.len().unwrap_or(0);
+                if len > 0 {
+                    let mut items = Vec::with_capacity(len as usize);
+                    for index in 1..=len {
+                        // A value that fails to read becomes `Nil` so the
+                        // sequence keeps its length instead of silently
+                        // truncating at the first bad element.
+                        let item = match table.get::<_, rlua::Value>(index) {
+                            Ok(value) => LuaConfig::value_to_lua_type_free(&value),
+                            Err(_) => LuaType::Nil,
+                        };
+                        items.push(item);
+                    }
+                    LuaType::Array(items)
+                } else {
+                    let mut map = std::collections::HashMap::new();
+                    for pair in table.clone().pairs::<String, rlua::Value>() {
+                        if let Ok((key, value)) = pair {
+                            map.insert(key, LuaConfig::value_to_lua_type_free(&value));
+                        }
                     }
+                    LuaType::Table(map)
                 }
-                LuaType::Table(map)
             }
             _ => unimplemented!("Conversion for this Lua type is not implemented yet"),
         }
@@ -338,3 +731,145 @@ impl std::fmt::Display for LuaConfig {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config_with(data: HashMap<String, LuaType>) -> LuaConfig {
+        LuaConfig {
+            data,
+            config: String::new(),
+            default: None,
+            functions: Vec::new(),
+            globals: Vec::new(),
+            sandbox: None,
+        }
+    }
+
+    fn sample() -> LuaConfig {
+        let mut server = HashMap::new();
+        server.insert("host".to_string(), LuaType::String("localhost".to_string()));
+        server.insert("port".to_string(), LuaType::Integer(8080));
+
+        let mut first = HashMap::new();
+        first.insert("host".to_string(), LuaType::String("a".to_string()));
+        let mut second = HashMap::new();
+        second.insert("host".to_string(), LuaType::String("b".to_string()));
+
+        let mut data = HashMap::new();
+        data.insert("server".to_string(), LuaType::Table(server));
+        data.insert(
+            "servers".to_string(),
+            LuaType::Array(vec![LuaType::Table(first), LuaType::Table(second)]),
+        );
+        config_with(data)
+    }
+
+    #[test]
+    fn descends_into_nested_tables() {
+        let config = sample();
+        assert!(matches!(
+            config.get_lua_type("server/host"),
+            Some(LuaType::String(s)) if s == "localhost"
+        ));
+        assert_eq!(config.get::<i64>("server/port"), Some(8080));
+    }
+
+    #[test]
+    fn indexes_arrays_one_based() {
+        let config = sample();
+        assert!(matches!(
+            config.get_lua_type("servers/1/host"),
+            Some(LuaType::String(s)) if s == "a"
+        ));
+        assert!(matches!(
+            config.get_lua_type("servers/2/host"),
+            Some(LuaType::String(s)) if s == "b"
+        ));
+    }
+
+    #[test]
+    fn array_index_zero_is_none() {
+        assert!(sample().get_lua_type("servers/0/host").is_none());
+    }
+
+    #[test]
+    fn array_index_out_of_range_is_none() {
+        assert!(sample().get_lua_type("servers/3/host").is_none());
+    }
+
+    #[test]
+    fn type_mismatch_is_none() {
+        // `host` is a string, so descending further must fail cleanly.
+        assert!(sample().get_lua_type("server/host/extra").is_none());
+        // A missing top-level key is also `None`.
+        assert!(sample().get_lua_type("missing").is_none());
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Server {
+        host: String,
+        port: i64,
+    }
+
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Config {
+        name: String,
+        retries: Option<u32>,
+        missing: Option<u32>,
+        ports: Vec<i64>,
+        servers: Vec<Server>,
+        env: HashMap<String, String>,
+    }
+
+    #[test]
+    fn deserializes_nested_struct() {
+        let mut server = HashMap::new();
+        server.insert("host".to_string(), LuaType::String("localhost".to_string()));
+        server.insert("port".to_string(), LuaType::Integer(8080));
+
+        let mut env = HashMap::new();
+        env.insert("HOME".to_string(), LuaType::String("/root".to_string()));
+
+        let mut data = HashMap::new();
+        data.insert("name".to_string(), LuaType::String("app".to_string()));
+        data.insert("retries".to_string(), LuaType::Integer(3));
+        data.insert("missing".to_string(), LuaType::Nil);
+        data.insert(
+            "ports".to_string(),
+            LuaType::Array(vec![LuaType::Integer(80), LuaType::Integer(443)]),
+        );
+        data.insert(
+            "servers".to_string(),
+            LuaType::Array(vec![LuaType::Table(server)]),
+        );
+        data.insert("env".to_string(), LuaType::Table(env));
+
+        let config = LuaConfig {
+            data,
+            config: String::new(),
+            default: None,
+            functions: Vec::new(),
+            globals: Vec::new(),
+            sandbox: None,
+        };
+
+        let parsed: Config = config.deserialize().unwrap();
+        assert_eq!(parsed.name, "app");
+        assert_eq!(parsed.retries, Some(3));
+        assert_eq!(parsed.missing, None);
+        assert_eq!(parsed.ports, vec![80, 443]);
+        assert_eq!(parsed.servers.len(), 1);
+        assert_eq!(parsed.servers[0].host, "localhost");
+        assert_eq!(parsed.servers[0].port, 8080);
+        assert_eq!(parsed.env.get("HOME").map(String::as_str), Some("/root"));
+    }
+}